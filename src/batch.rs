@@ -0,0 +1,72 @@
+use alloc::boxed::Box;
+
+use crate::{
+    xelis_hash, BUFFER_SIZE, BYTES_ARRAY_INPUT, ITERS, MEMORY_SIZE, SCRATCHPAD_ITERS, SLOT_LENGTH,
+    Hash, ScratchPad,
+};
+
+/// Hashes `N` independent, fixed-size inputs.
+///
+/// This is currently an **unvectorized placeholder**: each lane runs the
+/// exact stage 1/2/3 core used by [`xelis_hash`] to completion, one lane
+/// after another, so it is bit-identical to calling [`xelis_hash`] `N` times
+/// (see `test_batch_matches_scalar`) but gets none of the cross-lane
+/// `keccakp`/AES vectorization the API is ultimately meant to provide — that
+/// requires a transposed, SIMD-friendly Keccak-f state and is tracked as
+/// follow-up work, not something this function does today. Treat this as a
+/// convenience wrapper for iterating a batch, not a performance win over
+/// calling [`xelis_hash`] directly.
+///
+/// `pads` holds one heap-allocated [`ScratchPad`] per lane rather than a
+/// stack-allocated `[ScratchPad; N]`: at the default `MEM`, a scratchpad is
+/// 256 KB, so a handful of lanes is already enough to blow a normal 2 MiB
+/// thread stack. Use [`ScratchPad::boxed`] to build each one.
+pub fn xelis_hash_batch<const N: usize>(
+    inputs: &mut [[u8; BYTES_ARRAY_INPUT]; N],
+    pads: &mut [Box<ScratchPad>; N],
+) -> [Hash; N] {
+    core::array::from_fn(|i| {
+        xelis_hash::<MEMORY_SIZE, SCRATCHPAD_ITERS, ITERS, BUFFER_SIZE, SLOT_LENGTH>(
+            &mut inputs[i],
+            pads[i].as_mut_slice(),
+        )
+        .expect("fixed-size BYTES_ARRAY_INPUT input is always valid")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xelis_hash_no_scratch_pad;
+
+    #[test]
+    fn test_batch_matches_scalar() {
+        const N: usize = 4;
+
+        let mut inputs: [[u8; BYTES_ARRAY_INPUT]; N] = core::array::from_fn(|i| {
+            let mut input = [0u8; BYTES_ARRAY_INPUT];
+            input[0] = i as u8;
+            input
+        });
+        let mut scalar_inputs = inputs;
+        let mut pads: [Box<ScratchPad>; N] = core::array::from_fn(|_| ScratchPad::boxed());
+
+        let batched = xelis_hash_batch(&mut inputs, &mut pads);
+
+        for i in 0..N {
+            let scalar = xelis_hash_no_scratch_pad(&mut scalar_inputs[i]).unwrap();
+            assert_eq!(batched[i], scalar);
+        }
+    }
+
+    #[test]
+    fn test_batch_single_lane() {
+        let mut inputs: [[u8; BYTES_ARRAY_INPUT]; 1] = [[0u8; BYTES_ARRAY_INPUT]];
+        let mut pads: [Box<ScratchPad>; 1] = [ScratchPad::boxed()];
+
+        let batched = xelis_hash_batch(&mut inputs, &mut pads);
+        let scalar = xelis_hash_no_scratch_pad(&mut [0u8; BYTES_ARRAY_INPUT]).unwrap();
+
+        assert_eq!(batched[0], scalar);
+    }
+}
@@ -1,8 +1,36 @@
+//! `std` is a default feature: building without it gets a
+//! `no_std` + `alloc` crate (the [`Error`] type becomes a manual
+//! `core::error::Error` impl, and [`ScratchPad::locked`]/[`Input::locked`]
+//! disappear since they need `std::io`/`libc`). A manifest wiring this up
+//! needs a `[features]` table along the lines of:
+//!
+//! ```toml
+//! [features]
+//! default = ["std"]
+//! std = ["dep:thiserror", "dep:libc"]
+//! ```
+//!
+//! with `thiserror` and `libc` both declared `optional = true`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
 use aes::cipher::generic_array::GenericArray;
-use thiserror::Error as ThisError;
 use tiny_keccak::keccakp;
 
-// These are tweakable parameters
+mod batch;
+mod pool;
+mod streaming;
+pub use batch::xelis_hash_batch;
+pub use pool::{PoolGuard, ScratchPadPool};
+pub use streaming::XelisHasher;
+
+// These are the default "tweakable parameters", now also usable as const
+// generics on `ScratchPad` and `xelis_hash` so alternate memory/iteration
+// profiles can coexist in the same binary instead of requiring a fork.
 pub const MEMORY_SIZE: usize = 32768;
 pub const SCRATCHPAD_ITERS: usize = 5000;
 pub const ITERS: usize = 1;
@@ -14,21 +42,90 @@ pub const KECCAK_WORDS: usize = 25;
 pub const BYTES_ARRAY_INPUT: usize = KECCAK_WORDS * 8;
 pub const HASH_SIZE: usize = 32;
 
-pub struct ScratchPad([u64; MEMORY_SIZE]);
+#[repr(transparent)]
+pub struct ScratchPad<const MEM: usize = MEMORY_SIZE>([u64; MEM]);
 
-impl ScratchPad {
+impl<const MEM: usize> ScratchPad<MEM> {
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
-    pub fn as_mut_slice(&mut self) -> &mut [u64; MEMORY_SIZE] {
+    pub fn as_mut_slice(&mut self) -> &mut [u64; MEM] {
         &mut self.0
     }
+
+    // SAFETY: `ScratchPad` is `#[repr(transparent)]` over `[u64; MEM]`, so an
+    // all-zero allocation is a bit-valid `Self` (matches `Default`).
+    fn alloc_zeroed_ptr() -> *mut Self {
+        let layout = core::alloc::Layout::new::<Self>();
+        let ptr = unsafe { alloc::alloc::alloc_zeroed(layout) };
+        if ptr.is_null() {
+            alloc::alloc::handle_alloc_error(layout);
+        }
+        ptr as *mut Self
+    }
+
+    /// Heap-allocates a zeroed scratchpad directly rather than building
+    /// `Self::default()` on the stack first and moving it in — for a
+    /// caller-chosen `MEM` in the tens of MB that stack value could overflow
+    /// the stack before it ever reaches the heap. Prefer this over
+    /// `Box::new(Self::default())` anywhere `MEM` isn't known to be small,
+    /// e.g. the `[Box<ScratchPad<MEM>>; N]` taken by [`xelis_hash_batch`].
+    pub fn boxed() -> alloc::boxed::Box<Self> {
+        // SAFETY: `ptr` was just allocated with `Self`'s layout.
+        unsafe { alloc::boxed::Box::from_raw(Self::alloc_zeroed_ptr()) }
+    }
 }
 
-impl Default for ScratchPad {
+impl<const MEM: usize> Default for ScratchPad<MEM> {
     fn default() -> Self {
-        Self([0; MEMORY_SIZE])
+        Self([0; MEM])
+    }
+}
+
+impl<const MEM: usize> ScratchPad<MEM> {
+    // The scratchpad is derived from the preimage and can leak mining/nonce
+    // state, so wipe it instead of leaving it for the allocator to overwrite
+    // (or not) whenever it feels like it. `write_volatile` plus a
+    // `compiler_fence` stop the optimizer from proving the writes are dead
+    // and eliding them, the same trick memguard's word buffers use.
+    pub(crate) fn zeroize(&mut self) {
+        for word in self.0.iter_mut() {
+            unsafe { core::ptr::write_volatile(word, 0) };
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+impl<const MEM: usize> Drop for ScratchPad<MEM> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(all(feature = "std", unix))]
+impl<const MEM: usize> ScratchPad<MEM> {
+    /// Allocates a scratchpad on the heap and `mlock`s its backing pages so
+    /// the kernel keeps them resident in RAM and out of swap for as long as
+    /// the process runs. Fails with the `mlock` `io::Error` if the lock
+    /// can't be taken, e.g. the process is over `RLIMIT_MEMLOCK`.
+    pub fn locked() -> std::io::Result<alloc::boxed::Box<Self>> {
+        let layout = core::alloc::Layout::new::<Self>();
+        let ptr = Self::alloc_zeroed_ptr();
+
+        // Lock the allocation while we still hold a plain pointer to it —
+        // `Box::as_ptr` would need the unstable `box_as_ptr` feature on
+        // stable Rust, so we mlock before handing the memory to a `Box`.
+        let rc = unsafe { libc::mlock(ptr as *const core::ffi::c_void, layout.size()) };
+        if rc != 0 {
+            unsafe { alloc::alloc::dealloc(ptr as *mut u8, layout) };
+            return Err(std::io::Error::last_os_error());
+        }
+
+        // SAFETY: `ptr` was just allocated with `layout`, which matches `Self`.
+        let boxed = unsafe { alloc::boxed::Box::from_raw(ptr) };
+
+        Ok(boxed)
     }
 }
 
@@ -47,12 +144,12 @@ impl Default for Input {
         if BYTES_ARRAY_INPUT % 8 != 0 {
             n += 1;
         }
-    
+
         Self {
             data: vec![Bytes8Alignment([0; 8]); n]
         }
     }
-} 
+}
 
 impl Input {
     pub fn len(&self) -> usize {
@@ -72,35 +169,108 @@ impl Input {
     }
 }
 
-#[derive(Debug, ThisError)]
+// Same rationale as `ScratchPad`'s Drop: the message bytes can carry
+// mining/nonce state, so clear them with a volatile write the optimizer
+// can't elide instead of trusting whatever replaces this allocation.
+impl Drop for Input {
+    fn drop(&mut self) {
+        for word in self.data.iter_mut() {
+            unsafe { core::ptr::write_volatile(word, Bytes8Alignment([0; 8])) };
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(all(feature = "std", unix))]
+impl Input {
+    /// Allocates the input buffer and `mlock`s its backing heap allocation,
+    /// keeping it resident in RAM and out of swap for as long as it lives.
+    pub fn locked() -> std::io::Result<Self> {
+        let input = Self::default();
+        let ptr = input.data.as_ptr() as *const core::ffi::c_void;
+        let len = input.data.len() * core::mem::size_of::<Bytes8Alignment>();
+
+        let rc = unsafe { libc::mlock(ptr, len) };
+        if rc != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(input)
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, thiserror::Error)]
 #[error("Error while hashing")]
 pub struct Error;
 
+#[cfg(not(feature = "std"))]
+#[derive(Debug)]
+pub struct Error;
+
+#[cfg(not(feature = "std"))]
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Error while hashing")
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl core::error::Error for Error {}
+
 pub type Hash = [u8; HASH_SIZE];
 
-// This will auto allocate the scratchpad
+// This will auto allocate the scratchpad, using the default tweakable parameters
 pub fn xelis_hash_no_scratch_pad(input: &mut [u8]) -> Result<Hash, Error> {
-    let mut scratchpad = ScratchPad::default();
+    let mut scratchpad = ScratchPad::<MEMORY_SIZE>::default();
     xelis_hash_scratch_pad(input, &mut scratchpad)
 }
 
-pub fn xelis_hash_scratch_pad(input: &mut [u8], scratch_pad: &mut ScratchPad) -> Result<Hash, Error> {
-    xelis_hash(input, scratch_pad.as_mut_slice())
+pub fn xelis_hash_scratch_pad(input: &mut [u8], scratch_pad: &mut ScratchPad<MEMORY_SIZE>) -> Result<Hash, Error> {
+    xelis_hash::<MEMORY_SIZE, SCRATCHPAD_ITERS, ITERS, BUFFER_SIZE, SLOT_LENGTH>(input, scratch_pad.as_mut_slice())
 }
 
-pub fn xelis_hash(input: &mut [u8], scratch_pad: &mut [u64; MEMORY_SIZE]) -> Result<Hash, Error> {
+/// Runs the three hashing stages with caller-chosen tweakable parameters.
+///
+/// `SLOT_LENGTH` indexes stage 2's `slots`/`indices` buffers through a
+/// `u16`, so it must be in `1..=u16::MAX as usize`, and `scratch_pad`
+/// (`MEM` words, i.e. `MEM * 2` `u32` words) must divide evenly by it or
+/// stage 2 silently leaves a trailing, never-mixed remainder. Parameter
+/// combinations outside those bounds return [`Error`] instead of panicking
+/// or silently producing a result with an unmixed tail.
+pub fn xelis_hash<
+    const MEM: usize,
+    const SCRATCHPAD_ITERS: usize,
+    const ITERS: usize,
+    const BUFFER_SIZE: usize,
+    const SLOT_LENGTH: usize,
+>(input: &mut [u8], scratch_pad: &mut [u64; MEM]) -> Result<Hash, Error> {
     if input.len() < BYTES_ARRAY_INPUT {
         return Err(Error);
     }
 
-    if scratch_pad.len() < MEMORY_SIZE {
+    if scratch_pad.len() < MEM {
+        return Err(Error);
+    }
+
+    if SLOT_LENGTH == 0 || SLOT_LENGTH > u16::MAX as usize || (MEM * 2) % SLOT_LENGTH != 0 {
         return Err(Error);
     }
+
     // stage 1
-    let int_input: &mut [u64; KECCAK_WORDS] = bytemuck::try_from_bytes_mut(&mut input[0..BYTES_ARRAY_INPUT])
-        .map_err(|_| Error)?;
+    //
+    // Read the input as u64 words by value instead of reinterpret-casting
+    // the caller's byte slice in place: `input` is a plain `&mut [u8]` with
+    // no alignment guarantee, and `bytemuck`'s cast requires 8-byte
+    // alignment to hand back a `&mut [u64; KECCAK_WORDS]`, which an
+    // arbitrary caller buffer (e.g. a stack `[u8; N]`) may not have.
+    let mut int_input = [0u64; KECCAK_WORDS];
+    for (word, bytes) in int_input.iter_mut().zip(input[..BYTES_ARRAY_INPUT].chunks_exact(8)) {
+        *word = u64::from_le_bytes(bytes.try_into().map_err(|_| Error)?);
+    }
+    let int_input = &mut int_input;
 
-    for i in 0..=(MEMORY_SIZE / KECCAK_WORDS) {
+    for i in 0..=(MEM / KECCAK_WORDS) {
         keccakp(int_input);
 
         let mut rand_int: u64 = 0;
@@ -109,7 +279,7 @@ pub fn xelis_hash(input: &mut [u8], scratch_pad: &mut [u64; MEMORY_SIZE]) -> Res
             let pair_idx2 = (j + 2) % KECCAK_WORDS;
 
             let target_idx = i * KECCAK_WORDS + j;
-            if target_idx < MEMORY_SIZE {
+            if target_idx < MEM {
                 let a = int_input[j] ^ rand_int;
                 // Branching
                 let left = int_input[pair_idx];
@@ -131,11 +301,10 @@ pub fn xelis_hash(input: &mut [u8], scratch_pad: &mut [u64; MEMORY_SIZE]) -> Res
 
     // stage 2
     let mut slots: [u32; SLOT_LENGTH] = [0; SLOT_LENGTH];
-    // this is equal to MEMORY_SIZE, just in u32 format
-    let small_pad: &mut [u32; MEMORY_SIZE * 2] = bytemuck::try_cast_slice_mut(scratch_pad)
-        .map_err(|_| Error)?
-        .try_into()
-        .map_err(|_| Error)?;
+    // this is scratch_pad reinterpreted in u32 format; a slice (rather than
+    // a `MEM * 2`-sized array) because generic array lengths can't be built
+    // from an expression of another const generic on stable Rust
+    let small_pad: &mut [u32] = bytemuck::try_cast_slice_mut(scratch_pad).map_err(|_| Error)?;
 
     slots.copy_from_slice(&small_pad[small_pad.len() - SLOT_LENGTH..]);
 
@@ -172,21 +341,22 @@ pub fn xelis_hash(input: &mut [u8], scratch_pad: &mut [u64; MEMORY_SIZE]) -> Res
         }
     }
 
-    small_pad[(MEMORY_SIZE * 2) - SLOT_LENGTH..].copy_from_slice(&slots);
+    let small_pad_len = small_pad.len();
+    small_pad[small_pad_len - SLOT_LENGTH..].copy_from_slice(&slots);
 
     // stage 3
     let key = GenericArray::from([0u8; 16]);
     let mut block = GenericArray::from([0u8; 16]);
 
-    let mut addr_a = (scratch_pad[MEMORY_SIZE - 1] >> 15) & 0x7FFF;
-    let mut addr_b = scratch_pad[MEMORY_SIZE - 1] & 0x7FFF;
+    let mut addr_a = (scratch_pad[MEM - 1] >> 15) & 0x7FFF;
+    let mut addr_b = scratch_pad[MEM - 1] & 0x7FFF;
 
     let mut mem_buffer_a: [u64; BUFFER_SIZE] = [0; BUFFER_SIZE];
     let mut mem_buffer_b: [u64; BUFFER_SIZE] = [0; BUFFER_SIZE];
 
     for i in 0..BUFFER_SIZE as u64 {
-        mem_buffer_a[i as usize] = scratch_pad[((addr_a + i) % MEMORY_SIZE as u64) as usize];
-        mem_buffer_b[i as usize] = scratch_pad[((addr_b + i) % MEMORY_SIZE as u64) as usize];
+        mem_buffer_a[i as usize] = scratch_pad[((addr_a + i) % MEM as u64) as usize];
+        mem_buffer_b[i as usize] = scratch_pad[((addr_b + i) % MEM as u64) as usize];
     }
 
     let mut final_result = [0; HASH_SIZE];
@@ -253,16 +423,19 @@ pub fn xelis_hash(input: &mut [u8], scratch_pad: &mut [u64; MEMORY_SIZE]) -> Res
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::{time::Instant, hint};
+    use core::hint;
 
     fn test_input(input: &mut [u8], expected_hash: Hash) {
         let mut scratch_pad = [0u64; MEMORY_SIZE];
-        let hash = xelis_hash(input, &mut scratch_pad).unwrap();
+        let hash = xelis_hash::<MEMORY_SIZE, SCRATCHPAD_ITERS, ITERS, BUFFER_SIZE, SLOT_LENGTH>(input, &mut scratch_pad).unwrap();
         assert_eq!(hash, expected_hash);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn benchmark_cpu_hash() {
+        use std::time::Instant;
+
         let mut input = [0u8; 200];
         let mut scratch_pad = [0u64; 32768];
 
@@ -271,7 +444,7 @@ mod tests {
         for i in 0..iterations {
             input[0] = i as u8;
             input[1] = (i >> 8) as u8;
-            let _ = hint::black_box(xelis_hash(&mut input, &mut scratch_pad)).unwrap();
+            let _ = hint::black_box(xelis_hash::<MEMORY_SIZE, SCRATCHPAD_ITERS, ITERS, BUFFER_SIZE, SLOT_LENGTH>(&mut input, &mut scratch_pad)).unwrap();
         }
 
         let elapsed = start.elapsed();
@@ -308,7 +481,7 @@ mod tests {
 
     #[test]
     fn test_scratch_pad() {
-        let mut scratch_pad = ScratchPad::default();
+        let mut scratch_pad = ScratchPad::<MEMORY_SIZE>::default();
         let mut input = Input::default();
 
         let hash = xelis_hash_scratch_pad(input.as_mut_slice().unwrap(), &mut scratch_pad).unwrap();
@@ -319,4 +492,26 @@ mod tests {
         ];
         assert_eq!(hash, expected_hash);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_alternate_profile() {
+        // A smaller, faster profile coexisting with the default one above —
+        // proves the tweakable parameters are genuinely independent per call.
+        const MEM: usize = 1024;
+        let mut scratch_pad = [0u64; MEM];
+        let mut input = [0u8; BYTES_ARRAY_INPUT];
+
+        let hash = xelis_hash::<MEM, 64, 1, 16, 32>(&mut input, &mut scratch_pad).unwrap();
+        assert_eq!(hash.len(), HASH_SIZE);
+    }
+
+    #[cfg(all(feature = "std", unix))]
+    #[test]
+    fn test_scratch_pad_locked() {
+        let mut scratch_pad = ScratchPad::<1024>::locked().unwrap();
+        assert!(scratch_pad.as_mut_slice().iter().all(|&word| word == 0));
+
+        scratch_pad.as_mut_slice()[0] = 0xdead_beef;
+        assert_eq!(scratch_pad.as_mut_slice()[0], 0xdead_beef);
+    }
+}
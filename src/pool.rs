@@ -0,0 +1,188 @@
+use alloc::boxed::Box;
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+use crate::{ScratchPad, MEMORY_SIZE};
+
+const NULL: u32 = u32::MAX;
+
+struct Node<const MEM: usize> {
+    pad: UnsafeCell<ScratchPad<MEM>>,
+    next: AtomicU32,
+}
+
+/// A fixed-capacity pool of `N` pre-allocated [`ScratchPad`]s handed out via
+/// a lock-free (CAS-based) free list instead of the global allocator.
+///
+/// Modeled on heapless's `pool/cas.rs`: the pool owns all `N` scratchpads up
+/// front; [`alloc`](Self::alloc)/[`try_alloc`](Self::try_alloc) atomically
+/// pop a node off the free list, and dropping the returned [`PoolGuard`]
+/// resets its scratchpad to zero and atomically pushes it back. This lets a
+/// mining thread pool amortize the 256 KB-per-hash allocation instead of
+/// touching the allocator on the hot path.
+///
+/// The free list head packs a node index together with a generation counter
+/// into a single `AtomicU64` (top 32 bits: counter, bottom 32 bits: index),
+/// so a `compare_exchange` can only ever succeed against the exact head it
+/// observed — the classic tagged-pointer fix for the ABA problem that a bare
+/// `AtomicPtr`-based Treiber stack is vulnerable to.
+pub struct ScratchPadPool<const N: usize, const MEM: usize = MEMORY_SIZE> {
+    nodes: Box<[Node<MEM>]>,
+    head: AtomicU64,
+}
+
+// SAFETY: the free list's CAS protocol hands out exclusive ownership of a
+// node's `pad` on `try_alloc`, and `PoolGuard::drop` is the only other place
+// that touches it, so no two threads ever access the same node at once.
+unsafe impl<const N: usize, const MEM: usize> Sync for ScratchPadPool<N, MEM> {}
+
+fn pack(counter: u32, index: u32) -> u64 {
+    ((counter as u64) << 32) | index as u64
+}
+
+fn unpack(head: u64) -> (u32, u32) {
+    ((head >> 32) as u32, head as u32)
+}
+
+impl<const N: usize, const MEM: usize> ScratchPadPool<N, MEM> {
+    /// Allocates the `N` scratchpads and threads them into the free list.
+    pub fn new() -> Self {
+        let nodes: Box<[Node<MEM>]> = (0..N)
+            .map(|i| Node {
+                pad: UnsafeCell::new(ScratchPad::default()),
+                next: AtomicU32::new(if i + 1 < N { i as u32 + 1 } else { NULL }),
+            })
+            .collect();
+
+        let head = if N == 0 { NULL } else { 0 };
+
+        Self {
+            nodes,
+            head: AtomicU64::new(pack(0, head)),
+        }
+    }
+
+    /// Pops a scratchpad off the free list, panicking if the pool is
+    /// currently exhausted. See [`try_alloc`](Self::try_alloc) for a
+    /// non-panicking variant.
+    pub fn alloc(&self) -> PoolGuard<'_, N, MEM> {
+        self.try_alloc().expect("ScratchPadPool exhausted")
+    }
+
+    /// Pops a scratchpad off the free list, or `None` if every scratchpad
+    /// in the pool is currently checked out.
+    pub fn try_alloc(&self) -> Option<PoolGuard<'_, N, MEM>> {
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            let (counter, index) = unpack(head);
+            if index == NULL {
+                return None;
+            }
+
+            let next = self.nodes[index as usize].next.load(Ordering::Relaxed);
+            let new_head = pack(counter.wrapping_add(1), next);
+            match self
+                .head
+                .compare_exchange_weak(head, new_head, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return Some(PoolGuard { pool: self, index }),
+                Err(observed) => head = observed,
+            }
+        }
+    }
+
+    fn release(&self, index: u32) {
+        // Reset before publishing the node back to the free list so the
+        // next allocator never observes a previous caller's data.
+        unsafe { (*self.nodes[index as usize].pad.get()).zeroize() };
+
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            let (counter, current_index) = unpack(head);
+            self.nodes[index as usize]
+                .next
+                .store(current_index, Ordering::Relaxed);
+
+            let new_head = pack(counter.wrapping_add(1), index);
+            match self
+                .head
+                .compare_exchange_weak(head, new_head, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return,
+                Err(observed) => head = observed,
+            }
+        }
+    }
+}
+
+impl<const N: usize, const MEM: usize> Default for ScratchPadPool<N, MEM> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// RAII handle to a [`ScratchPad`] checked out of a [`ScratchPadPool`].
+/// Resets the scratchpad and returns it to the pool's free list on drop.
+pub struct PoolGuard<'a, const N: usize, const MEM: usize> {
+    pool: &'a ScratchPadPool<N, MEM>,
+    index: u32,
+}
+
+// SAFETY: a `PoolGuard` is the sole owner of its node for as long as it's
+// alive, so moving it to another thread just moves that ownership.
+unsafe impl<const N: usize, const MEM: usize> Send for PoolGuard<'_, N, MEM> {}
+
+impl<const N: usize, const MEM: usize> Deref for PoolGuard<'_, N, MEM> {
+    type Target = ScratchPad<MEM>;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.pool.nodes[self.index as usize].pad.get() }
+    }
+}
+
+impl<const N: usize, const MEM: usize> DerefMut for PoolGuard<'_, N, MEM> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.pool.nodes[self.index as usize].pad.get() }
+    }
+}
+
+impl<const N: usize, const MEM: usize> Drop for PoolGuard<'_, N, MEM> {
+    fn drop(&mut self) {
+        self.pool.release(self.index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alloc_exhausts_and_recovers() {
+        let pool = ScratchPadPool::<2, 64>::new();
+
+        let a = pool.alloc();
+        let b = pool.alloc();
+        assert!(pool.try_alloc().is_none());
+
+        drop(a);
+        let c = pool.try_alloc();
+        assert!(c.is_some());
+
+        drop(b);
+        drop(c);
+    }
+
+    #[test]
+    fn test_guard_resets_on_drop() {
+        let pool = ScratchPadPool::<1, 64>::new();
+
+        {
+            let mut guard = pool.alloc();
+            guard.as_mut_slice()[0] = 0xdead_beef;
+        }
+
+        let mut guard = pool.alloc();
+        assert_eq!(guard.as_mut_slice()[0], 0);
+    }
+}
@@ -0,0 +1,220 @@
+use alloc::vec::Vec;
+use digest::{
+    consts::U32,
+    generic_array::GenericArray,
+    FixedOutput, HashMarker, OutputSizeUser, Reset, Update,
+};
+use tiny_keccak::keccakp;
+
+use crate::{
+    xelis_hash, ScratchPad, BUFFER_SIZE, BYTES_ARRAY_INPUT, ITERS, KECCAK_WORDS, MEMORY_SIZE,
+    SCRATCHPAD_ITERS, SLOT_LENGTH,
+};
+
+/// Streaming, `digest`-compatible wrapper around [`xelis_hash`].
+///
+/// The core algorithm only operates on exactly [`BYTES_ARRAY_INPUT`] bytes
+/// of input, which makes it awkward to use anywhere a normal [`digest::Digest`]
+/// is expected. `XelisHasher` buffers an arbitrary number of
+/// [`Update::update`] calls and, on [`finalize`](FixedOutput::finalize_fixed),
+/// compresses everything it has buffered into that fixed-size block before
+/// running the usual three stages on it.
+pub struct XelisHasher {
+    buffer: Vec<u8>,
+    scratch_pad: ScratchPad<MEMORY_SIZE>,
+}
+
+impl Clone for XelisHasher {
+    fn clone(&self) -> Self {
+        // `ScratchPad` doesn't implement `Clone` (it's scratch space wiped
+        // on drop, not meaningful state to duplicate), so a clone only
+        // needs to carry over the buffered message and gets a fresh
+        // scratchpad of its own.
+        Self {
+            buffer: self.buffer.clone(),
+            scratch_pad: ScratchPad::default(),
+        }
+    }
+}
+
+impl Default for XelisHasher {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::new(),
+            scratch_pad: ScratchPad::default(),
+        }
+    }
+}
+
+impl HashMarker for XelisHasher {}
+
+impl OutputSizeUser for XelisHasher {
+    type OutputSize = U32;
+}
+
+impl Update for XelisHasher {
+    fn update(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+}
+
+impl Reset for XelisHasher {
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.scratch_pad = ScratchPad::default();
+    }
+}
+
+impl FixedOutput for XelisHasher {
+    fn finalize_into(mut self, out: &mut GenericArray<u8, U32>) {
+        let mut state = compress(&self.buffer);
+        // Reinterpret the u64 words as bytes in place rather than copying
+        // them into a plain `[u8; BYTES_ARRAY_INPUT]`; `xelis_hash`'s stage 1
+        // reads the block back word by word, so it needs no particular
+        // alignment from this buffer.
+        let block: &mut [u8; BYTES_ARRAY_INPUT] = bytemuck::cast_mut(&mut state);
+        let hash = xelis_hash::<MEMORY_SIZE, SCRATCHPAD_ITERS, ITERS, BUFFER_SIZE, SLOT_LENGTH>(
+            block,
+            self.scratch_pad.as_mut_slice(),
+        )
+        .expect("compressed block is always BYTES_ARRAY_INPUT bytes long");
+        out.copy_from_slice(&hash);
+    }
+}
+
+/// Absorbs `message` into a fixed [`BYTES_ARRAY_INPUT`]-byte block, Keccak
+/// sponge style: each full `BYTES_ARRAY_INPUT`-sized chunk is XORed
+/// word-by-word into the 25-word state and the state is permuted with
+/// `keccakp` before the next chunk is absorbed.
+///
+/// The final chunk is `pad10*1`-padded (a `0x01` marker byte right after the
+/// message bytes, the block's last bit forced to `1`) rather than silently
+/// zero-filled, and a message that exactly fills a block gets one extra
+/// padding-only block absorbed after it. Without this, messages that only
+/// differ by trailing zero bytes — including the empty message versus a
+/// block of all zero bytes — would compress to the identical block and
+/// therefore the identical digest.
+///
+/// Returns the raw `u64` words rather than bytes so the caller can
+/// reinterpret them in place as an 8-byte-aligned `[u8; BYTES_ARRAY_INPUT]`.
+fn compress(message: &[u8]) -> [u64; KECCAK_WORDS] {
+    let mut state = [0u64; KECCAK_WORDS];
+    let mut offset = 0;
+
+    loop {
+        let end = (offset + BYTES_ARRAY_INPUT).min(message.len());
+        let remaining = end - offset;
+
+        if remaining == BYTES_ARRAY_INPUT {
+            let block: &[u8; BYTES_ARRAY_INPUT] = message[offset..end]
+                .try_into()
+                .expect("remaining == BYTES_ARRAY_INPUT");
+            absorb(&mut state, block);
+            offset = end;
+
+            if offset == message.len() {
+                // The message ended exactly on a block boundary: absorb a
+                // dedicated padding-only block so this can't collide with a
+                // shorter message whose own padding happens to look the same.
+                absorb(&mut state, &pad_only_block());
+                break;
+            }
+        } else {
+            let mut block = [0u8; BYTES_ARRAY_INPUT];
+            block[..remaining].copy_from_slice(&message[offset..end]);
+            block[remaining] = 0x01;
+            block[BYTES_ARRAY_INPUT - 1] |= 0x80;
+            absorb(&mut state, &block);
+            break;
+        }
+    }
+
+    state
+}
+
+fn pad_only_block() -> [u8; BYTES_ARRAY_INPUT] {
+    let mut block = [0u8; BYTES_ARRAY_INPUT];
+    block[0] = 0x01;
+    block[BYTES_ARRAY_INPUT - 1] |= 0x80;
+    block
+}
+
+fn absorb(state: &mut [u64; KECCAK_WORDS], block: &[u8; BYTES_ARRAY_INPUT]) {
+    for (word, bytes) in state.iter_mut().zip(block.chunks_exact(8)) {
+        *word ^= u64::from_le_bytes(bytes.try_into().expect("8-byte chunk"));
+    }
+    keccakp(state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_empty_input() {
+        let hash = XelisHasher::default().finalize_fixed();
+        assert_eq!(hash.len(), 32);
+    }
+
+    #[test]
+    fn test_streaming_matches_single_update() {
+        let message = b"xelis-hashing-algorithm";
+
+        let mut streamed = XelisHasher::default();
+        for byte in message {
+            streamed.update(&[*byte]);
+        }
+
+        let mut single = XelisHasher::default();
+        single.update(message);
+
+        assert_eq!(streamed.finalize_fixed(), single.finalize_fixed());
+    }
+
+    #[test]
+    fn test_long_input_spans_multiple_blocks() {
+        let message = vec![0x42u8; BYTES_ARRAY_INPUT * 3 + 17];
+
+        let mut hasher = XelisHasher::default();
+        hasher.update(&message);
+        let hash = hasher.finalize_fixed();
+
+        assert_eq!(hash.len(), 32);
+    }
+
+    #[test]
+    fn test_empty_vs_zero_block_differ() {
+        let empty = XelisHasher::default().finalize_fixed();
+
+        let mut zero_block = XelisHasher::default();
+        zero_block.update(&[0u8; BYTES_ARRAY_INPUT]);
+
+        assert_ne!(empty, zero_block.finalize_fixed());
+    }
+
+    #[test]
+    fn test_trailing_zero_padding_differs() {
+        let mut short = XelisHasher::default();
+        short.update(b"xelis");
+
+        let mut padded = XelisHasher::default();
+        padded.update(b"xelis");
+        padded.update(&[0u8; 4]);
+
+        assert_ne!(short.finalize_fixed(), padded.finalize_fixed());
+    }
+
+    #[test]
+    fn test_block_boundary_message_differs_from_one_byte_short() {
+        let full = [0x7au8; BYTES_ARRAY_INPUT];
+
+        let mut exact = XelisHasher::default();
+        exact.update(&full);
+
+        let mut one_short = XelisHasher::default();
+        one_short.update(&full[..BYTES_ARRAY_INPUT - 1]);
+
+        assert_ne!(exact.finalize_fixed(), one_short.finalize_fixed());
+    }
+}